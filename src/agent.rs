@@ -1,29 +1,188 @@
 use crate::error::{DynamodeError, Result};
-use crate::model::DynamoModel;
-use aws_sdk_dynamodb::config::Region;
-use aws_sdk_dynamodb::types::AttributeValue;
+use crate::model::{DynamoModel, IndexSchema, TableSchema};
+use aws_sdk_dynamodb::config::{retry::RetryConfig, Credentials, Region, TimeoutConfig};
+use aws_sdk_dynamodb::operation::describe_table::DescribeTableOutput;
+use aws_sdk_dynamodb::types::{
+    AttributeDefinition, AttributeValue, BillingMode, CreateGlobalSecondaryIndexAction,
+    DeleteRequest, GlobalSecondaryIndex, GlobalSecondaryIndexUpdate, IndexStatus,
+    KeySchemaElement, KeyType, Projection, ProjectionType, PutRequest, ScalarAttributeType,
+    TableStatus, WriteRequest,
+};
 use aws_sdk_dynamodb::Client;
 use serde::{de::DeserializeOwned, Serialize};
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// DynamoDB caps a single `BatchWriteItem` call at this many write requests.
+const BATCH_WRITE_CHUNK_SIZE: usize = 25;
+/// How many times to re-submit `UnprocessedItems` before giving up.
+const BATCH_WRITE_MAX_RETRIES: u32 = 5;
+/// Table tracking which (model table name, schema hash) migrations have run.
+const MIGRATIONS_TABLE_NAME: &str = "DynamodeMigrations";
+/// How long to wait between `DescribeTable` polls while a table/index settles.
+const WAIT_POLL_INTERVAL: Duration = Duration::from_secs(2);
+/// How many polls to attempt before giving up on a table/index becoming ACTIVE.
+const MAX_WAIT_ATTEMPTS: u32 = 60;
 
 pub struct DynamodeAgent {
     pub client: Client,
 }
 
+/// Connection configuration for `DynamodeAgent::connect`. Every field is
+/// optional; anything left unset falls back to the AWS default provider
+/// chain (environment, profile, or instance role).
+#[derive(Debug, Clone, Default)]
+pub struct DynamodeConfig {
+    pub region: Option<String>,
+    pub endpoint_url: Option<String>,
+    pub credentials: Option<StaticCredentials>,
+    pub timeout: Option<Duration>,
+    /// Maximum number of attempts (including the first) for a request before
+    /// giving up, passed to the SDK's standard retry mode. Leaving this unset
+    /// falls back to the SDK default (3 attempts).
+    pub max_attempts: Option<u32>,
+}
+
+/// Static access-key credentials, as an alternative to the AWS default
+/// provider chain.
+#[derive(Debug, Clone)]
+pub struct StaticCredentials {
+    pub access_key_id: String,
+    pub secret_access_key: String,
+    pub session_token: Option<String>,
+}
+
+/// A condition on a composite table's sort key, for narrowing `query` beyond
+/// its partition key (e.g. all cars whose model sort key begins with
+/// "model-").
+#[derive(Debug, Clone)]
+pub enum SortKeyCondition {
+    Equals(String),
+    BeginsWith(String),
+    Between(String, String),
+    GreaterThan(String),
+    GreaterThanOrEqual(String),
+    LessThan(String),
+    LessThanOrEqual(String),
+}
+
+impl SortKeyCondition {
+    fn key_condition_clause(&self, key_placeholder: &str) -> String {
+        match self {
+            SortKeyCondition::Equals(_) => format!("{key_placeholder} = :skv"),
+            SortKeyCondition::BeginsWith(_) => format!("begins_with({key_placeholder}, :skv)"),
+            SortKeyCondition::Between(_, _) => {
+                format!("{key_placeholder} BETWEEN :sklo AND :skhi")
+            }
+            SortKeyCondition::GreaterThan(_) => format!("{key_placeholder} > :skv"),
+            SortKeyCondition::GreaterThanOrEqual(_) => format!("{key_placeholder} >= :skv"),
+            SortKeyCondition::LessThan(_) => format!("{key_placeholder} < :skv"),
+            SortKeyCondition::LessThanOrEqual(_) => format!("{key_placeholder} <= :skv"),
+        }
+    }
+
+    /// Builds this condition's expression attribute values, encoding each one
+    /// as `key_type` (the declared type of the sort/range key being compared
+    /// against) rather than always assuming `S`.
+    fn expression_attribute_values(
+        &self,
+        key_type: &ScalarAttributeType,
+    ) -> Result<Vec<(&'static str, AttributeValue)>> {
+        match self {
+            SortKeyCondition::Equals(v) | SortKeyCondition::BeginsWith(v) => {
+                Ok(vec![(":skv", scalar_attribute_value(v, key_type)?)])
+            }
+            SortKeyCondition::Between(lo, hi) => Ok(vec![
+                (":sklo", scalar_attribute_value(lo, key_type)?),
+                (":skhi", scalar_attribute_value(hi, key_type)?),
+            ]),
+            SortKeyCondition::GreaterThan(v)
+            | SortKeyCondition::GreaterThanOrEqual(v)
+            | SortKeyCondition::LessThan(v)
+            | SortKeyCondition::LessThanOrEqual(v) => {
+                Ok(vec![(":skv", scalar_attribute_value(v, key_type)?)])
+            }
+        }
+    }
+}
+
+/// Encodes a key value as the DynamoDB `AttributeValue` variant matching
+/// `attr_type`, so callers aren't limited to string-typed keys/indexes.
+/// Binary values are expected base64-encoded, as with the `$binary` JSON
+/// wrapper.
+fn scalar_attribute_value(value: &str, attr_type: &ScalarAttributeType) -> Result<AttributeValue> {
+    match attr_type {
+        ScalarAttributeType::N => Ok(AttributeValue::N(value.to_string())),
+        ScalarAttributeType::B => Ok(AttributeValue::B(decode_binary(value)?)),
+        _ => Ok(AttributeValue::S(value.to_string())),
+    }
+}
+
 impl DynamodeAgent {
-    /// Connects to DynamoDB running locally at http://localhost:8000
-    pub async fn connect_local() -> Self {
-        let region = Region::new("us-west-2");
+    /// Connects using an explicit `DynamodeConfig`, so the same binary can
+    /// target local, staging, and production DynamoDB without recompiling.
+    pub async fn connect(config: DynamodeConfig) -> Self {
         let shared_config = aws_config::load_defaults(aws_config::BehaviorVersion::latest()).await;
+        let mut builder = aws_sdk_dynamodb::config::Builder::from(&shared_config);
 
-        let dynamo_config = aws_sdk_dynamodb::config::Builder::from(&shared_config)
-            .region(region) //
-            .endpoint_url("http://localhost:8000")
-            .build();
+        if let Some(region) = config.region {
+            builder = builder.region(Region::new(region));
+        }
+        if let Some(endpoint_url) = config.endpoint_url {
+            builder = builder.endpoint_url(endpoint_url);
+        }
+        if let Some(credentials) = config.credentials {
+            builder = builder.credentials_provider(Credentials::new(
+                credentials.access_key_id,
+                credentials.secret_access_key,
+                credentials.session_token,
+                None,
+                "dynamode-static",
+            ));
+        }
+        if let Some(timeout) = config.timeout {
+            builder = builder.timeout_config(
+                TimeoutConfig::builder()
+                    .operation_timeout(timeout)
+                    .build(),
+            );
+        }
+        if let Some(max_attempts) = config.max_attempts {
+            builder = builder.retry_config(RetryConfig::standard().with_max_attempts(max_attempts));
+        }
 
-        let client = Client::from_conf(dynamo_config);
+        let client = Client::from_conf(builder.build());
         Self { client }
     }
 
+    /// Connects using `DYNAMODE_REGION`/`DYNAMODE_ENDPOINT_URL` environment
+    /// variables when set, falling back to the AWS default provider chain
+    /// (region/credentials from the environment, profile, or instance role).
+    pub async fn connect_from_env() -> Self {
+        Self::connect(DynamodeConfig {
+            region: std::env::var("DYNAMODE_REGION").ok(),
+            endpoint_url: std::env::var("DYNAMODE_ENDPOINT_URL").ok(),
+            credentials: None,
+            timeout: None,
+            max_attempts: std::env::var("DYNAMODE_MAX_ATTEMPTS")
+                .ok()
+                .and_then(|s| s.parse().ok()),
+        })
+        .await
+    }
+
+    /// Connects to DynamoDB running locally at http://localhost:8000
+    pub async fn connect_local() -> Self {
+        Self::connect(DynamodeConfig {
+            region: Some("us-west-2".to_string()),
+            endpoint_url: Some("http://localhost:8000".to_string()),
+            credentials: None,
+            timeout: None,
+            max_attempts: None,
+        })
+        .await
+    }
+
     /// Put item into DynamoDB
     pub async fn put<M: DynamoModel + Serialize>(&self, item: &M) -> Result<()> {
         let table_name = M::table_name();
@@ -49,6 +208,107 @@ impl DynamodeAgent {
         Ok(())
     }
 
+    /// Put an item only if `condition` (a DynamoDB condition expression)
+    /// holds, for compare-and-set writes. `values` maps each value
+    /// placeholder referenced in `condition` (e.g. `:expected`) to its
+    /// `serde_json::Value`. Returns `DynamodeError::ConditionFailed` if the
+    /// condition does not hold.
+    pub async fn put_if<M: DynamoModel + Serialize>(
+        &self,
+        item: &M,
+        condition: &str,
+        values: HashMap<String, serde_json::Value>,
+    ) -> Result<()> {
+        let table_name = M::table_name();
+        let item_json =
+            serde_json::to_value(item).map_err(|e| DynamodeError::Serialization(e.to_string()))?;
+        let mut item_map = HashMap::new();
+        if let serde_json::Value::Object(map) = item_json {
+            for (k, v) in map {
+                item_map.insert(k, json_value_to_av(v)?);
+            }
+        }
+
+        let mut expr_attr_values = HashMap::new();
+        for (placeholder, value) in values {
+            expr_attr_values.insert(placeholder, json_value_to_av(value)?);
+        }
+
+        self.client
+            .put_item()
+            .table_name(table_name)
+            .set_item(Some(item_map))
+            .condition_expression(condition)
+            .set_expression_attribute_values(Some(expr_attr_values))
+            .send()
+            .await
+            .map_err(|e| {
+                if e.as_service_error()
+                    .map(|se| se.is_conditional_check_failed_exception())
+                    .unwrap_or(false)
+                {
+                    DynamodeError::ConditionFailed
+                } else {
+                    DynamodeError::DynamoDb(e.to_string())
+                }
+            })?;
+
+        Ok(())
+    }
+
+    /// Put an item with optimistic locking: the model's current `version()`
+    /// is the caller's expected stored version, and the write asserts
+    /// `attribute_not_exists(pk) OR #version = :expected` while incrementing
+    /// the stored version. Returns `DynamodeError::ConditionFailed` on a
+    /// stale write (another writer already advanced the version).
+    pub async fn put_versioned<M: DynamoModel + Serialize>(&self, item: &M) -> Result<()> {
+        let table_name = M::table_name();
+        let item_json =
+            serde_json::to_value(item).map_err(|e| DynamodeError::Serialization(e.to_string()))?;
+        let mut item_map = HashMap::new();
+        if let serde_json::Value::Object(map) = item_json {
+            for (k, v) in map {
+                item_map.insert(k, json_value_to_av(v)?);
+            }
+        }
+
+        let expected_version = item.version().unwrap_or(0);
+        let next_version = expected_version + 1;
+        item_map.insert("version".to_string(), AttributeValue::N(next_version.to_string()));
+
+        let mut expr_attr_names = HashMap::new();
+        expr_attr_names.insert("#pk".to_string(), "pk".to_string());
+        expr_attr_names.insert("#version".to_string(), "version".to_string());
+
+        let mut expr_attr_values = HashMap::new();
+        expr_attr_values.insert(
+            ":expected".to_string(),
+            AttributeValue::N(expected_version.to_string()),
+        );
+
+        self.client
+            .put_item()
+            .table_name(table_name)
+            .set_item(Some(item_map))
+            .condition_expression("attribute_not_exists(#pk) OR #version = :expected")
+            .set_expression_attribute_names(Some(expr_attr_names))
+            .set_expression_attribute_values(Some(expr_attr_values))
+            .send()
+            .await
+            .map_err(|e| {
+                if e.as_service_error()
+                    .map(|se| se.is_conditional_check_failed_exception())
+                    .unwrap_or(false)
+                {
+                    DynamodeError::ConditionFailed
+                } else {
+                    DynamodeError::DynamoDb(e.to_string())
+                }
+            })?;
+
+        Ok(())
+    }
+
     /// Get item by (pk, sk)
     pub async fn get<M: DynamoModel + DeserializeOwned>(
         &self,
@@ -89,6 +349,71 @@ impl DynamodeAgent {
         self.put(item).await
     }
 
+    /// Partially update an item via `UpdateItem`, without re-serializing or
+    /// overwriting the rest of the record. A `Value::Null` entry in `changes`
+    /// removes that attribute instead of setting it.
+    pub async fn update_fields<M: DynamoModel>(
+        &self,
+        keys: (String, String),
+        changes: &HashMap<String, serde_json::Value>,
+    ) -> Result<()> {
+        let table_name = M::table_name();
+        let (pk, sk) = keys;
+        let mut key_map = HashMap::new();
+        key_map.insert("pk".to_string(), AttributeValue::S(pk));
+        key_map.insert("sk".to_string(), AttributeValue::S(sk));
+
+        let mut set_clauses = Vec::new();
+        let mut remove_clauses = Vec::new();
+        let mut expr_attr_names = HashMap::new();
+        let mut expr_attr_values = HashMap::new();
+
+        for (i, (field, value)) in changes.iter().enumerate() {
+            let name_ph = format!("#f{i}");
+            expr_attr_names.insert(name_ph.clone(), field.clone());
+
+            if value.is_null() {
+                remove_clauses.push(name_ph);
+            } else {
+                let value_ph = format!(":v{i}");
+                expr_attr_values.insert(value_ph.clone(), json_value_to_av(value.clone())?);
+                set_clauses.push(format!("{name_ph} = {value_ph}"));
+            }
+        }
+
+        let mut update_expression = String::new();
+        if !set_clauses.is_empty() {
+            update_expression.push_str("SET ");
+            update_expression.push_str(&set_clauses.join(", "));
+        }
+        if !remove_clauses.is_empty() {
+            if !update_expression.is_empty() {
+                update_expression.push(' ');
+            }
+            update_expression.push_str("REMOVE ");
+            update_expression.push_str(&remove_clauses.join(", "));
+        }
+
+        let mut request = self
+            .client
+            .update_item()
+            .table_name(table_name)
+            .set_key(Some(key_map))
+            .update_expression(update_expression)
+            .set_expression_attribute_names(Some(expr_attr_names));
+
+        if !expr_attr_values.is_empty() {
+            request = request.set_expression_attribute_values(Some(expr_attr_values));
+        }
+
+        request
+            .send()
+            .await
+            .map_err(|e| DynamodeError::DynamoDb(e.to_string()))?;
+
+        Ok(())
+    }
+
     /// Delete an item by (pk, sk)
     pub async fn delete<M: DynamoModel>(&self, keys: (String, String)) -> Result<()> {
         let table_name = M::table_name();
@@ -108,17 +433,120 @@ impl DynamodeAgent {
         Ok(())
     }
 
+    /// Delete an item only if `condition` (a DynamoDB condition expression)
+    /// holds. Returns `DynamodeError::ConditionFailed` if it does not.
+    pub async fn delete_if<M: DynamoModel>(
+        &self,
+        keys: (String, String),
+        condition: &str,
+        values: HashMap<String, serde_json::Value>,
+    ) -> Result<()> {
+        let table_name = M::table_name();
+        let (pk, sk) = keys;
+        let mut key_map = HashMap::new();
+        key_map.insert("pk".to_string(), AttributeValue::S(pk));
+        key_map.insert("sk".to_string(), AttributeValue::S(sk));
+
+        let mut expr_attr_values = HashMap::new();
+        for (placeholder, value) in values {
+            expr_attr_values.insert(placeholder, json_value_to_av(value)?);
+        }
+
+        self.client
+            .delete_item()
+            .table_name(table_name)
+            .set_key(Some(key_map))
+            .condition_expression(condition)
+            .set_expression_attribute_values(Some(expr_attr_values))
+            .send()
+            .await
+            .map_err(|e| {
+                if e.as_service_error()
+                    .map(|se| se.is_conditional_check_failed_exception())
+                    .unwrap_or(false)
+                {
+                    DynamodeError::ConditionFailed
+                } else {
+                    DynamodeError::DynamoDb(e.to_string())
+                }
+            })?;
+
+        Ok(())
+    }
+
     /// Query all items with a given partition key (e.g. all cars for "bmw")
     pub async fn query_by_pk<M: DynamoModel + DeserializeOwned>(
         &self,
         pk_value: String,
+    ) -> Result<Vec<M>> {
+        self.query(pk_value, None).await
+    }
+
+    /// Query items by partition key, optionally narrowed by a
+    /// `SortKeyCondition` (e.g. all Tesla models whose sort key begins with
+    /// "model-").
+    pub async fn query<M: DynamoModel + DeserializeOwned>(
+        &self,
+        pk: String,
+        sk: Option<SortKeyCondition>,
     ) -> Result<Vec<M>> {
         let table_name = M::table_name();
-        let mut expr_attr_names = std::collections::HashMap::new();
+
+        let mut key_condition_expression = "#pk = :pk_val".to_string();
+        let mut expr_attr_names = HashMap::new();
         expr_attr_names.insert("#pk".to_string(), "pk".to_string());
 
-        let mut expr_attr_vals = std::collections::HashMap::new();
-        expr_attr_vals.insert(":pk_val".to_string(), AttributeValue::S(pk_value.clone()));
+        let mut expr_attr_values = HashMap::new();
+        expr_attr_values.insert(":pk_val".to_string(), AttributeValue::S(pk));
+
+        if let Some(condition) = &sk {
+            expr_attr_names.insert("#sk".to_string(), "sk".to_string());
+            key_condition_expression.push_str(" AND ");
+            key_condition_expression.push_str(&condition.key_condition_clause("#sk"));
+            for (name, value) in condition.expression_attribute_values(&ScalarAttributeType::S)? {
+                expr_attr_values.insert(name.to_string(), value);
+            }
+        }
+
+        let mut results = Vec::new();
+        let mut exclusive_start_key = None;
+
+        loop {
+            let resp = self
+                .client
+                .query()
+                .table_name(table_name)
+                .key_condition_expression(&key_condition_expression)
+                .set_expression_attribute_names(Some(expr_attr_names.clone()))
+                .set_expression_attribute_values(Some(expr_attr_values.clone()))
+                .set_exclusive_start_key(exclusive_start_key)
+                .send()
+                .await
+                .map_err(|e| DynamodeError::DynamoDb(e.to_string()))?;
+
+            if let Some(items) = resp.items {
+                for item in items {
+                    results.push(item_to_model(item)?);
+                }
+            }
+
+            exclusive_start_key = resp.last_evaluated_key;
+            if exclusive_start_key.is_none() {
+                break;
+            }
+        }
+
+        Ok(results)
+    }
+
+    /// Query a single page for a given partition key, returning the page's
+    /// items plus a cursor (`LastEvaluatedKey`) for fetching the next page.
+    pub async fn query_page<M: DynamoModel + DeserializeOwned>(
+        &self,
+        pk_value: String,
+        exclusive_start_key: Option<HashMap<String, AttributeValue>>,
+    ) -> Result<(Vec<M>, Option<HashMap<String, AttributeValue>>)> {
+        let table_name = M::table_name();
 
         let resp = self
             .client
@@ -127,6 +555,7 @@ impl DynamodeAgent {
             .key_condition_expression("#pk = :pk_val")
             .expression_attribute_names("#pk", "pk")
             .expression_attribute_values(":pk_val", AttributeValue::S(pk_value))
+            .set_exclusive_start_key(exclusive_start_key)
             .send()
             .await
             .map_err(|e| DynamodeError::DynamoDb(e.to_string()))?;
@@ -134,27 +563,232 @@ impl DynamodeAgent {
         let mut results = Vec::new();
         if let Some(items) = resp.items {
             for item in items {
-                let mut map = serde_json::Map::new();
-                for (k, v) in item {
-                    map.insert(k, av_to_json_value(&v)?);
+                results.push(item_to_model(item)?);
+            }
+        }
+        Ok((results, resp.last_evaluated_key))
+    }
+
+    /// Put a batch of items, splitting into chunks of `BATCH_WRITE_CHUNK_SIZE`
+    /// (DynamoDB's per-call limit) and retrying any `UnprocessedItems` with
+    /// exponential backoff.
+    pub async fn batch_put<M: DynamoModel + Serialize>(&self, items: &[M]) -> Result<()> {
+        let table_name = M::table_name();
+
+        for chunk in items.chunks(BATCH_WRITE_CHUNK_SIZE) {
+            let mut requests = Vec::with_capacity(chunk.len());
+            for item in chunk {
+                let item_json = serde_json::to_value(item)
+                    .map_err(|e| DynamodeError::Serialization(e.to_string()))?;
+                let mut item_map = HashMap::new();
+                if let serde_json::Value::Object(map) = item_json {
+                    for (k, v) in map {
+                        item_map.insert(k, json_value_to_av(v)?);
+                    }
                 }
-                let json = serde_json::Value::Object(map);
-                let model: M = serde_json::from_value(json)
-                    .map_err(|e| DynamodeError::Deserialization(e.to_string()))?;
-                results.push(model);
+
+                let put_request = PutRequest::builder()
+                    .set_item(Some(item_map))
+                    .build()
+                    .map_err(|e| DynamodeError::Serialization(e.to_string()))?;
+                requests.push(WriteRequest::builder().put_request(put_request).build());
             }
+
+            self.submit_write_requests(table_name, requests).await?;
         }
+
+        Ok(())
+    }
+
+    /// Delete a batch of items by `(pk, sk)`, chunked and retried the same way
+    /// as `batch_put`.
+    pub async fn batch_delete<M: DynamoModel>(&self, keys: &[(String, String)]) -> Result<()> {
+        let table_name = M::table_name();
+
+        for chunk in keys.chunks(BATCH_WRITE_CHUNK_SIZE) {
+            let mut requests = Vec::with_capacity(chunk.len());
+            for (pk, sk) in chunk {
+                let mut key_map = HashMap::new();
+                key_map.insert("pk".to_string(), AttributeValue::S(pk.clone()));
+                key_map.insert("sk".to_string(), AttributeValue::S(sk.clone()));
+
+                let delete_request = DeleteRequest::builder()
+                    .set_key(Some(key_map))
+                    .build()
+                    .map_err(|e| DynamodeError::Serialization(e.to_string()))?;
+                requests.push(WriteRequest::builder().delete_request(delete_request).build());
+            }
+
+            self.submit_write_requests(table_name, requests).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Submit a chunk (<= 25) of write requests, resubmitting any
+    /// `UnprocessedItems` the table returns until the map drains or
+    /// `BATCH_WRITE_MAX_RETRIES` is exceeded.
+    async fn submit_write_requests(
+        &self,
+        table_name: &str,
+        requests: Vec<WriteRequest>,
+    ) -> Result<()> {
+        let mut pending: HashMap<String, Vec<WriteRequest>> =
+            HashMap::from([(table_name.to_string(), requests)]);
+        let mut attempt = 0u32;
+
+        loop {
+            let output = self
+                .client
+                .batch_write_item()
+                .set_request_items(Some(std::mem::take(&mut pending)))
+                .send()
+                .await
+                .map_err(|e| DynamodeError::DynamoDb(e.to_string()))?;
+
+            pending = output.unprocessed_items.unwrap_or_default();
+            if pending.is_empty() {
+                return Ok(());
+            }
+
+            attempt += 1;
+            if attempt > BATCH_WRITE_MAX_RETRIES {
+                return Err(DynamodeError::DynamoDb(format!(
+                    "BatchWriteItem: {} unprocessed write request(s) remained after {} retries",
+                    pending.values().map(Vec::len).sum::<usize>(),
+                    BATCH_WRITE_MAX_RETRIES
+                )));
+            }
+
+            let backoff = Duration::from_millis(100 * 2u64.pow(attempt - 1));
+            tokio::time::sleep(backoff).await;
+        }
+    }
+
+    /// Query items by a global secondary index declared in
+    /// `M::schema().global_secondary_indexes` (e.g. look cars up by `brand`
+    /// instead of scanning the whole table).
+    pub async fn query_by_index<M: DynamoModel + DeserializeOwned>(
+        &self,
+        index_name: &str,
+        hash_value: String,
+        range: Option<SortKeyCondition>,
+    ) -> Result<Vec<M>> {
+        let table_name = M::table_name();
+        let schema = M::schema();
+        let index = schema
+            .global_secondary_indexes
+            .iter()
+            .find(|idx| idx.name == index_name)
+            .ok_or_else(|| {
+                DynamodeError::Validation(format!("Unknown index \"{index_name}\""))
+            })?;
+        let (hash_attr, range_attr, range_type) =
+            (index.hash_name, index.range_name, index.range_type.clone());
+
+        let mut key_condition_expression = "#hk = :hv".to_string();
+        let mut expr_attr_names = HashMap::new();
+        expr_attr_names.insert("#hk".to_string(), hash_attr.to_string());
+
+        let mut expr_attr_values = HashMap::new();
+        expr_attr_values.insert(
+            ":hv".to_string(),
+            scalar_attribute_value(&hash_value, &index.hash_type)?,
+        );
+
+        if let Some(condition) = &range {
+            let range_attr = range_attr.ok_or_else(|| {
+                DynamodeError::Validation(format!("Index \"{index_name}\" has no range key"))
+            })?;
+            let range_type = range_type.ok_or_else(|| {
+                DynamodeError::Validation(format!(
+                    "Index \"{index_name}\" has no range key type"
+                ))
+            })?;
+            expr_attr_names.insert("#rk".to_string(), range_attr.to_string());
+            key_condition_expression.push_str(" AND ");
+            key_condition_expression.push_str(&condition.key_condition_clause("#rk"));
+            for (name, value) in condition.expression_attribute_values(&range_type)? {
+                expr_attr_values.insert(name.to_string(), value);
+            }
+        }
+
+        let mut results = Vec::new();
+        let mut exclusive_start_key = None;
+
+        loop {
+            let resp = self
+                .client
+                .query()
+                .table_name(table_name)
+                .index_name(index_name)
+                .key_condition_expression(&key_condition_expression)
+                .set_expression_attribute_names(Some(expr_attr_names.clone()))
+                .set_expression_attribute_values(Some(expr_attr_values.clone()))
+                .set_exclusive_start_key(exclusive_start_key)
+                .send()
+                .await
+                .map_err(|e| DynamodeError::DynamoDb(e.to_string()))?;
+
+            if let Some(items) = resp.items {
+                for item in items {
+                    results.push(item_to_model(item)?);
+                }
+            }
+
+            exclusive_start_key = resp.last_evaluated_key;
+            if exclusive_start_key.is_none() {
+                break;
+            }
+        }
+
         Ok(results)
     }
 
     /// Scan all items in the table (admin/debug only!)
     pub async fn scan_all<M: DynamoModel + DeserializeOwned>(&self) -> Result<Vec<M>> {
         let table_name = M::table_name();
+        let mut results = Vec::new();
+        let mut exclusive_start_key = None;
+
+        loop {
+            let resp = self
+                .client
+                .scan()
+                .table_name(table_name)
+                .set_exclusive_start_key(exclusive_start_key)
+                .send()
+                .await
+                .map_err(|e| DynamodeError::DynamoDb(e.to_string()))?;
+
+            if let Some(items) = resp.items {
+                for item in items {
+                    results.push(item_to_model(item)?);
+                }
+            }
+
+            exclusive_start_key = resp.last_evaluated_key;
+            if exclusive_start_key.is_none() {
+                break;
+            }
+        }
+
+        Ok(results)
+    }
+
+    /// Scan a single page, returning the page's items plus a cursor
+    /// (`LastEvaluatedKey`) for fetching the next page.
+    pub async fn scan_page<M: DynamoModel + DeserializeOwned>(
+        &self,
+        exclusive_start_key: Option<HashMap<String, AttributeValue>>,
+    ) -> Result<(Vec<M>, Option<HashMap<String, AttributeValue>>)> {
+        let table_name = M::table_name();
 
         let resp = self
             .client
             .scan()
             .table_name(table_name)
+            .set_exclusive_start_key(exclusive_start_key)
             .send()
             .await
             .map_err(|e| DynamodeError::DynamoDb(e.to_string()))?;
@@ -162,18 +796,400 @@ impl DynamodeAgent {
         let mut results = Vec::new();
         if let Some(items) = resp.items {
             for item in items {
-                let mut map = serde_json::Map::new();
-                for (k, v) in item {
-                    map.insert(k, av_to_json_value(&v)?);
+                results.push(item_to_model(item)?);
+            }
+        }
+        Ok((results, resp.last_evaluated_key))
+    }
+
+    /// Ensure `M`'s table (and any GSIs declared in `M::schema()`) exist and
+    /// are `ACTIVE`, creating or updating them as needed. Applied migrations
+    /// are recorded in a `DynamodeMigrations` metadata table keyed by model
+    /// table name + schema hash, so calling this at startup is idempotent.
+    pub async fn migrate<M: DynamoModel>(&self) -> Result<()> {
+        let schema = M::schema();
+
+        if schema.pk_type != ScalarAttributeType::S
+            || schema.sk_type.as_ref().is_some_and(|t| *t != ScalarAttributeType::S)
+        {
+            return Err(DynamodeError::Validation(format!(
+                "Table \"{}\": pk_type/sk_type must be ScalarAttributeType::S; \
+                 every key-building CRUD path assumes a string primary key",
+                schema.table_name
+            )));
+        }
+
+        let schema_hash = schema_hash(&schema);
+
+        if self
+            .migration_applied(schema.table_name, &schema_hash)
+            .await?
+        {
+            return Ok(());
+        }
+
+        match self
+            .client
+            .describe_table()
+            .table_name(schema.table_name)
+            .send()
+            .await
+        {
+            Ok(describe) => self.apply_missing_indexes(&schema, describe).await?,
+            Err(e) => {
+                if e.as_service_error()
+                    .map(|se| se.is_resource_not_found_exception())
+                    .unwrap_or(false)
+                {
+                    self.create_table(&schema).await?;
+                } else {
+                    return Err(DynamodeError::DynamoDb(e.to_string()));
                 }
-                let json = serde_json::Value::Object(map);
-                let model: M = serde_json::from_value(json)
-                    .map_err(|e| DynamodeError::Deserialization(e.to_string()))?;
-                results.push(model);
             }
         }
-        Ok(results)
+
+        self.wait_until_active(schema.table_name).await?;
+        self.record_migration(schema.table_name, &schema_hash)
+            .await?;
+        Ok(())
+    }
+
+    async fn create_table(&self, schema: &TableSchema) -> Result<()> {
+        // Indexed by attribute name so that a GSI reusing the table's own
+        // pk/sk (the "overloaded index" pattern), or two GSIs sharing a hash
+        // attribute, doesn't produce duplicate `AttributeDefinition` entries
+        // in the same `CreateTable` call - DynamoDB rejects those outright.
+        let mut attribute_definitions: std::collections::BTreeMap<&str, AttributeDefinition> =
+            std::collections::BTreeMap::new();
+        attribute_definitions.insert(
+            schema.pk_name,
+            AttributeDefinition::builder()
+                .attribute_name(schema.pk_name)
+                .attribute_type(schema.pk_type.clone())
+                .build()
+                .map_err(|e| DynamodeError::DynamoDb(e.to_string()))?,
+        );
+
+        let mut builder = self
+            .client
+            .create_table()
+            .table_name(schema.table_name)
+            .key_schema(
+                KeySchemaElement::builder()
+                    .attribute_name(schema.pk_name)
+                    .key_type(KeyType::Hash)
+                    .build()
+                    .map_err(|e| DynamodeError::DynamoDb(e.to_string()))?,
+            )
+            .billing_mode(schema.billing_mode.clone());
+
+        if let (Some(sk_name), Some(sk_type)) = (schema.sk_name, schema.sk_type.clone()) {
+            attribute_definitions.insert(
+                sk_name,
+                AttributeDefinition::builder()
+                    .attribute_name(sk_name)
+                    .attribute_type(sk_type)
+                    .build()
+                    .map_err(|e| DynamodeError::DynamoDb(e.to_string()))?,
+            );
+            builder = builder.key_schema(
+                KeySchemaElement::builder()
+                    .attribute_name(sk_name)
+                    .key_type(KeyType::Range)
+                    .build()
+                    .map_err(|e| DynamodeError::DynamoDb(e.to_string()))?,
+            );
+        }
+
+        for index in schema.global_secondary_indexes {
+            attribute_definitions.insert(
+                index.hash_name,
+                AttributeDefinition::builder()
+                    .attribute_name(index.hash_name)
+                    .attribute_type(index.hash_type.clone())
+                    .build()
+                    .map_err(|e| DynamodeError::DynamoDb(e.to_string()))?,
+            );
+            builder = builder.global_secondary_indexes(build_gsi(index)?);
+
+            if let (Some(range_name), Some(range_type)) =
+                (index.range_name, index.range_type.clone())
+            {
+                attribute_definitions.insert(
+                    range_name,
+                    AttributeDefinition::builder()
+                        .attribute_name(range_name)
+                        .attribute_type(range_type)
+                        .build()
+                        .map_err(|e| DynamodeError::DynamoDb(e.to_string()))?,
+                );
+            }
+        }
+
+        builder
+            .set_attribute_definitions(Some(attribute_definitions.into_values().collect()))
+            .send()
+            .await
+            .map_err(|e| DynamodeError::DynamoDb(e.to_string()))?;
+
+        Ok(())
     }
+
+    async fn apply_missing_indexes(
+        &self,
+        schema: &TableSchema,
+        describe: DescribeTableOutput,
+    ) -> Result<()> {
+        let existing: std::collections::HashSet<String> = describe
+            .table
+            .and_then(|t| t.global_secondary_indexes)
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|gsi| gsi.index_name)
+            .collect();
+
+        for index in schema.global_secondary_indexes {
+            if existing.contains(index.name) {
+                continue;
+            }
+
+            let mut attribute_definitions = vec![AttributeDefinition::builder()
+                .attribute_name(index.hash_name)
+                .attribute_type(index.hash_type.clone())
+                .build()
+                .map_err(|e| DynamodeError::DynamoDb(e.to_string()))?];
+            if let (Some(range_name), Some(range_type)) =
+                (index.range_name, index.range_type.clone())
+            {
+                attribute_definitions.push(
+                    AttributeDefinition::builder()
+                        .attribute_name(range_name)
+                        .attribute_type(range_type)
+                        .build()
+                        .map_err(|e| DynamodeError::DynamoDb(e.to_string()))?,
+                );
+            }
+
+            self.client
+                .update_table()
+                .table_name(schema.table_name)
+                .set_attribute_definitions(Some(attribute_definitions))
+                .global_secondary_index_updates(
+                    GlobalSecondaryIndexUpdate::builder()
+                        .create(
+                            CreateGlobalSecondaryIndexAction::builder()
+                                .index_name(index.name)
+                                .set_key_schema(Some(gsi_key_schema(index)?))
+                                .projection(
+                                    Projection::builder()
+                                        .projection_type(ProjectionType::All)
+                                        .build(),
+                                )
+                                .build()
+                                .map_err(|e| DynamodeError::DynamoDb(e.to_string()))?,
+                        )
+                        .build(),
+                )
+                .send()
+                .await
+                .map_err(|e| DynamodeError::DynamoDb(e.to_string()))?;
+
+            self.wait_until_active(schema.table_name).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn wait_until_active(&self, table_name: &str) -> Result<()> {
+        for _ in 0..MAX_WAIT_ATTEMPTS {
+            let describe = self
+                .client
+                .describe_table()
+                .table_name(table_name)
+                .send()
+                .await
+                .map_err(|e| DynamodeError::DynamoDb(e.to_string()))?;
+
+            let table = describe.table.ok_or_else(|| {
+                DynamodeError::DynamoDb(format!("DescribeTable returned no table for {table_name}"))
+            })?;
+
+            let table_active = matches!(table.table_status, Some(TableStatus::Active));
+            let indexes_active = table
+                .global_secondary_indexes
+                .unwrap_or_default()
+                .iter()
+                .all(|gsi| matches!(gsi.index_status, Some(IndexStatus::Active)));
+
+            if table_active && indexes_active {
+                return Ok(());
+            }
+
+            tokio::time::sleep(WAIT_POLL_INTERVAL).await;
+        }
+
+        Err(DynamodeError::DynamoDb(format!(
+            "Timed out waiting for table {table_name} to become ACTIVE"
+        )))
+    }
+
+    async fn ensure_migrations_table(&self) -> Result<()> {
+        match self
+            .client
+            .describe_table()
+            .table_name(MIGRATIONS_TABLE_NAME)
+            .send()
+            .await
+        {
+            Ok(_) => return Ok(()),
+            Err(e) => {
+                if !e
+                    .as_service_error()
+                    .map(|se| se.is_resource_not_found_exception())
+                    .unwrap_or(false)
+                {
+                    return Err(DynamodeError::DynamoDb(e.to_string()));
+                }
+            }
+        }
+
+        self.client
+            .create_table()
+            .table_name(MIGRATIONS_TABLE_NAME)
+            .attribute_definitions(
+                AttributeDefinition::builder()
+                    .attribute_name("pk")
+                    .attribute_type(ScalarAttributeType::S)
+                    .build()
+                    .map_err(|e| DynamodeError::DynamoDb(e.to_string()))?,
+            )
+            .attribute_definitions(
+                AttributeDefinition::builder()
+                    .attribute_name("sk")
+                    .attribute_type(ScalarAttributeType::S)
+                    .build()
+                    .map_err(|e| DynamodeError::DynamoDb(e.to_string()))?,
+            )
+            .key_schema(
+                KeySchemaElement::builder()
+                    .attribute_name("pk")
+                    .key_type(KeyType::Hash)
+                    .build()
+                    .map_err(|e| DynamodeError::DynamoDb(e.to_string()))?,
+            )
+            .key_schema(
+                KeySchemaElement::builder()
+                    .attribute_name("sk")
+                    .key_type(KeyType::Range)
+                    .build()
+                    .map_err(|e| DynamodeError::DynamoDb(e.to_string()))?,
+            )
+            .billing_mode(BillingMode::PayPerRequest)
+            .send()
+            .await
+            .map_err(|e| DynamodeError::DynamoDb(e.to_string()))?;
+
+        self.wait_until_active(MIGRATIONS_TABLE_NAME).await
+    }
+
+    async fn migration_applied(&self, model_table: &str, schema_hash: &str) -> Result<bool> {
+        self.ensure_migrations_table().await?;
+
+        let mut key_map = HashMap::new();
+        key_map.insert("pk".to_string(), AttributeValue::S(model_table.to_string()));
+        key_map.insert("sk".to_string(), AttributeValue::S(schema_hash.to_string()));
+
+        let output = self
+            .client
+            .get_item()
+            .table_name(MIGRATIONS_TABLE_NAME)
+            .set_key(Some(key_map))
+            .send()
+            .await
+            .map_err(|e| DynamodeError::DynamoDb(e.to_string()))?;
+
+        Ok(output.item.is_some())
+    }
+
+    async fn record_migration(&self, model_table: &str, schema_hash: &str) -> Result<()> {
+        let mut item_map = HashMap::new();
+        item_map.insert("pk".to_string(), AttributeValue::S(model_table.to_string()));
+        item_map.insert("sk".to_string(), AttributeValue::S(schema_hash.to_string()));
+
+        self.client
+            .put_item()
+            .table_name(MIGRATIONS_TABLE_NAME)
+            .set_item(Some(item_map))
+            .send()
+            .await
+            .map_err(|e| DynamodeError::DynamoDb(e.to_string()))?;
+
+        Ok(())
+    }
+}
+
+fn gsi_key_schema(index: &IndexSchema) -> Result<Vec<KeySchemaElement>> {
+    let mut key_schema = vec![KeySchemaElement::builder()
+        .attribute_name(index.hash_name)
+        .key_type(KeyType::Hash)
+        .build()
+        .map_err(|e| DynamodeError::DynamoDb(e.to_string()))?];
+
+    if let Some(range_name) = index.range_name {
+        key_schema.push(
+            KeySchemaElement::builder()
+                .attribute_name(range_name)
+                .key_type(KeyType::Range)
+                .build()
+                .map_err(|e| DynamodeError::DynamoDb(e.to_string()))?,
+        );
+    }
+
+    Ok(key_schema)
+}
+
+fn build_gsi(index: &IndexSchema) -> Result<GlobalSecondaryIndex> {
+    GlobalSecondaryIndex::builder()
+        .index_name(index.name)
+        .set_key_schema(Some(gsi_key_schema(index)?))
+        .projection(
+            Projection::builder()
+                .projection_type(ProjectionType::All)
+                .build(),
+        )
+        .build()
+        .map_err(|e| DynamodeError::DynamoDb(e.to_string()))
+}
+
+/// Hash a `TableSchema`'s shape so `migrate` can tell whether it has already
+/// been applied for a given model table.
+fn schema_hash(schema: &TableSchema) -> String {
+    use std::hash::{Hash, Hasher};
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    schema.table_name.hash(&mut hasher);
+    schema.pk_name.hash(&mut hasher);
+    format!("{:?}", schema.pk_type).hash(&mut hasher);
+    schema.sk_name.hash(&mut hasher);
+    format!("{:?}", schema.sk_type).hash(&mut hasher);
+    format!("{:?}", schema.billing_mode).hash(&mut hasher);
+    for index in schema.global_secondary_indexes {
+        index.name.hash(&mut hasher);
+        index.hash_name.hash(&mut hasher);
+        format!("{:?}", index.hash_type).hash(&mut hasher);
+        index.range_name.hash(&mut hasher);
+        format!("{:?}", index.range_type).hash(&mut hasher);
+    }
+    format!("{:x}", hasher.finish())
+}
+
+/// Convert a raw item map from a `GetItem`/`Query`/`Scan` response into `M`.
+fn item_to_model<M: DeserializeOwned>(item: HashMap<String, AttributeValue>) -> Result<M> {
+    let mut map = serde_json::Map::new();
+    for (k, v) in item {
+        map.insert(k, av_to_json_value(&v)?);
+    }
+    let json = serde_json::Value::Object(map);
+    serde_json::from_value(json).map_err(|e| DynamodeError::Deserialization(e.to_string()))
 }
 
 // Helper: Convert serde_json::Value to AttributeValue
@@ -203,6 +1219,58 @@ fn json_value_to_av(value: serde_json::Value) -> Result<AttributeValue> {
             Ok(AttributeValue::L(vals))
         }
         serde_json::Value::Object(map) => {
+            // Reserved single-key wrappers let a DynamoDB binary/set value
+            // round-trip through JSON instead of being flattened into an M.
+            if map.len() == 1 {
+                if let Some(b64) = map.get("$binary").and_then(|v| v.as_str()) {
+                    return Ok(AttributeValue::B(decode_binary(b64)?));
+                }
+                if let Some(arr) = map.get("$stringSet").and_then(|v| v.as_array()) {
+                    let ss = arr
+                        .iter()
+                        .map(|v| {
+                            v.as_str().map(str::to_string).ok_or_else(|| {
+                                DynamodeError::Serialization(
+                                    "$stringSet must contain only strings".to_string(),
+                                )
+                            })
+                        })
+                        .collect::<Result<Vec<_>>>()?;
+                    return Ok(AttributeValue::Ss(ss));
+                }
+                if let Some(arr) = map.get("$numberSet").and_then(|v| v.as_array()) {
+                    let ns = arr
+                        .iter()
+                        .map(|v| {
+                            if let Some(n) = v.as_i64() {
+                                Ok(n.to_string())
+                            } else if let Some(n) = v.as_f64() {
+                                Ok(n.to_string())
+                            } else {
+                                Err(DynamodeError::Serialization(
+                                    "$numberSet must contain only numbers".to_string(),
+                                ))
+                            }
+                        })
+                        .collect::<Result<Vec<_>>>()?;
+                    return Ok(AttributeValue::Ns(ns));
+                }
+                if let Some(arr) = map.get("$binarySet").and_then(|v| v.as_array()) {
+                    let bs = arr
+                        .iter()
+                        .map(|v| {
+                            let b64 = v.as_str().ok_or_else(|| {
+                                DynamodeError::Serialization(
+                                    "$binarySet must contain only base64 strings".to_string(),
+                                )
+                            })?;
+                            decode_binary(b64)
+                        })
+                        .collect::<Result<Vec<_>>>()?;
+                    return Ok(AttributeValue::Bs(bs));
+                }
+            }
+
             let mut av_map = std::collections::HashMap::new();
             for (k, v) in map {
                 av_map.insert(k, json_value_to_av(v)?);
@@ -212,6 +1280,15 @@ fn json_value_to_av(value: serde_json::Value) -> Result<AttributeValue> {
     }
 }
 
+/// Decode a base64-encoded `$binary`/`$binarySet` entry into a DynamoDB blob.
+fn decode_binary(b64: &str) -> Result<aws_smithy_types::Blob> {
+    use base64::Engine;
+    base64::engine::general_purpose::STANDARD
+        .decode(b64)
+        .map(aws_smithy_types::Blob::new)
+        .map_err(|e| DynamodeError::Deserialization(e.to_string()))
+}
+
 // Helper: Convert AttributeValue to serde_json::Value
 fn av_to_json_value(av: &AttributeValue) -> Result<serde_json::Value> {
     match av {
@@ -243,8 +1320,37 @@ fn av_to_json_value(av: &AttributeValue) -> Result<serde_json::Value> {
             }
             Ok(serde_json::Value::Object(json_map))
         }
+        AttributeValue::B(blob) => Ok(serde_json::json!({ "$binary": encode_binary(blob) })),
+        AttributeValue::Ss(set) => Ok(serde_json::json!({ "$stringSet": set })),
+        AttributeValue::Ns(set) => {
+            let nums: Vec<serde_json::Value> = set
+                .iter()
+                .map(|n| {
+                    if let Ok(i) = n.parse::<i64>() {
+                        serde_json::Value::Number(i.into())
+                    } else if let Ok(f) = n.parse::<f64>() {
+                        serde_json::Number::from_f64(f)
+                            .map(serde_json::Value::Number)
+                            .unwrap_or_else(|| serde_json::Value::String(n.clone()))
+                    } else {
+                        serde_json::Value::String(n.clone())
+                    }
+                })
+                .collect();
+            Ok(serde_json::json!({ "$numberSet": nums }))
+        }
+        AttributeValue::Bs(set) => {
+            let blobs: Vec<String> = set.iter().map(encode_binary).collect();
+            Ok(serde_json::json!({ "$binarySet": blobs }))
+        }
         _ => Err(DynamodeError::Deserialization(
             "Unsupported AttributeValue".to_string(),
         )),
     }
 }
+
+/// Base64-encode a DynamoDB blob for the `$binary`/`$binarySet` JSON wrapper.
+fn encode_binary(blob: &aws_smithy_types::Blob) -> String {
+    use base64::Engine;
+    base64::engine::general_purpose::STANDARD.encode(blob.as_ref())
+}
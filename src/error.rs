@@ -10,6 +10,7 @@ pub enum DynamodeError {
     InvalidKey,
     Validation(String),
     Network(String),
+    ConditionFailed,
 }
 
 impl fmt::Display for DynamodeError {
@@ -22,6 +23,7 @@ impl fmt::Display for DynamodeError {
             DynamodeError::InvalidKey => write!(f, "Invalid key"),
             DynamodeError::Validation(msg) => write!(f, "Validation error: {}", msg),
             DynamodeError::Network(msg) => write!(f, "Network error: {}", msg),
+            DynamodeError::ConditionFailed => write!(f, "Conditional check failed"),
         }
     }
 }
@@ -1,8 +1,6 @@
-use aws_sdk_dynamodb::types::{
-    AttributeDefinition, BillingMode, KeySchemaElement, KeyType, ScalarAttributeType,
-};
+use aws_sdk_dynamodb::types::{BillingMode, ScalarAttributeType};
 use dynamode::agent::DynamodeAgent;
-use dynamode::model::DynamoModel;
+use dynamode::model::{DynamoModel, IndexSchema, TableSchema};
 use serde_derive::{Deserialize, Serialize};
 
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
@@ -14,6 +12,10 @@ struct Car {
     horsepower: i32,
 }
 
+/// GSI letting cars be looked up by `brand` instead of scanning the whole
+/// table - see `DynamodeAgent::query_by_index`.
+const BRAND_INDEX: &str = "brand-index";
+
 #[async_trait::async_trait]
 impl DynamoModel for Car {
     fn table_name() -> &'static str {
@@ -22,57 +24,34 @@ impl DynamoModel for Car {
     fn partition_sort_key(&self) -> (String, String) {
         (self.pk.clone(), self.sk.clone())
     }
+    fn schema() -> TableSchema {
+        TableSchema {
+            table_name: "Cars",
+            pk_name: "pk",
+            pk_type: ScalarAttributeType::S,
+            sk_name: Some("sk"),
+            sk_type: Some(ScalarAttributeType::S),
+            billing_mode: BillingMode::PayPerRequest,
+            global_secondary_indexes: &[IndexSchema {
+                name: BRAND_INDEX,
+                hash_name: "brand",
+                hash_type: ScalarAttributeType::S,
+                range_name: None,
+                range_type: None,
+            }],
+        }
+    }
 }
 
 #[tokio::main]
 async fn main() {
     // Setup
     let agent = DynamodeAgent::connect_local().await;
-    let client = &agent.client;
-
-    // Create table if needed
-    let tables = client.list_tables().send().await.unwrap();
-    if !tables.table_names().contains(&"Cars".to_string()) {
-        println!("Creating Cars table...");
-        client
-            .create_table()
-            .table_name("Cars")
-            .attribute_definitions(
-                AttributeDefinition::builder()
-                    .attribute_name("pk")
-                    .attribute_type(ScalarAttributeType::S)
-                    .build()
-                    .unwrap(),
-            )
-            .attribute_definitions(
-                AttributeDefinition::builder()
-                    .attribute_name("sk")
-                    .attribute_type(ScalarAttributeType::S)
-                    .build()
-                    .unwrap(),
-            )
-            .key_schema(
-                KeySchemaElement::builder()
-                    .attribute_name("pk")
-                    .key_type(KeyType::Hash)
-                    .build()
-                    .unwrap(),
-            )
-            .key_schema(
-                KeySchemaElement::builder()
-                    .attribute_name("sk")
-                    .key_type(KeyType::Range)
-                    .build()
-                    .unwrap(),
-            )
-            .billing_mode(BillingMode::PayPerRequest)
-            .send()
-            .await
-            .unwrap();
 
-        println!("Cars table created!");
-    } else {
-        println!("Cars table already exists.");
+    // Create the Cars table if needed, or bring it up to date with the schema.
+    match agent.migrate::<Car>().await {
+        Ok(_) => println!("Cars table is ready."),
+        Err(e) => eprintln!("Migration failed: {}", e),
     }
 
     // Insert and fetch for demo
@@ -99,8 +78,43 @@ async fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use dynamode::agent::DynamodeConfig;
     use tokio;
 
+    #[tokio::test]
+    async fn test_connect_applies_explicit_config() {
+        let agent = DynamodeAgent::connect(DynamodeConfig {
+            region: Some("eu-west-1".to_string()),
+            endpoint_url: Some("http://localhost:8000".to_string()),
+            credentials: None,
+            timeout: None,
+            max_attempts: Some(5),
+        })
+        .await;
+
+        let config = agent.client.config();
+        assert_eq!(config.region().map(|r| r.as_ref()), Some("eu-west-1"));
+        assert_eq!(config.endpoint_url(), Some("http://localhost:8000"));
+    }
+
+    #[tokio::test]
+    async fn test_connect_from_env_reads_dynamode_vars() {
+        // SAFETY: no other test in this crate reads/writes these DYNAMODE_*
+        // vars, so there's no cross-test race on the process environment.
+        std::env::set_var("DYNAMODE_REGION", "ap-southeast-2");
+        std::env::set_var("DYNAMODE_ENDPOINT_URL", "http://localhost:8000");
+        std::env::set_var("DYNAMODE_MAX_ATTEMPTS", "7");
+
+        let agent = DynamodeAgent::connect_from_env().await;
+        let config = agent.client.config();
+        assert_eq!(config.region().map(|r| r.as_ref()), Some("ap-southeast-2"));
+        assert_eq!(config.endpoint_url(), Some("http://localhost:8000"));
+
+        std::env::remove_var("DYNAMODE_REGION");
+        std::env::remove_var("DYNAMODE_ENDPOINT_URL");
+        std::env::remove_var("DYNAMODE_MAX_ATTEMPTS");
+    }
+
     #[tokio::test]
     async fn test_insert_and_get_car() {
         let agent = DynamodeAgent::connect_local().await;
@@ -145,6 +159,47 @@ mod tests {
         assert_eq!(fetched.unwrap().horsepower, 503);
     }
 
+    #[tokio::test]
+    async fn test_update_fields_set_and_remove() {
+        let agent = DynamodeAgent::connect_local().await;
+
+        let car = Car {
+            pk: "honda".into(),
+            sk: "civic".into(),
+            brand: "Honda".into(),
+            model: "Civic".into(),
+            horsepower: 158,
+        };
+        agent.put(&car).await.expect("Insert failed");
+
+        let mut changes = std::collections::HashMap::new();
+        changes.insert("horsepower".to_string(), serde_json::json!(306));
+        changes.insert("model".to_string(), serde_json::Value::Null);
+
+        agent
+            .update_fields::<Car>(("honda".into(), "civic".into()), &changes)
+            .await
+            .expect("update_fields failed");
+
+        let output = agent
+            .client
+            .get_item()
+            .table_name(Car::table_name())
+            .key("pk", aws_sdk_dynamodb::types::AttributeValue::S("honda".into()))
+            .key("sk", aws_sdk_dynamodb::types::AttributeValue::S("civic".into()))
+            .send()
+            .await
+            .expect("Get failed")
+            .item
+            .expect("Item should still exist");
+
+        assert_eq!(
+            output.get("horsepower"),
+            Some(&aws_sdk_dynamodb::types::AttributeValue::N("306".to_string()))
+        );
+        assert!(!output.contains_key("model"));
+    }
+
     #[tokio::test]
     async fn test_delete_car() {
         let agent = DynamodeAgent::connect_local().await;
@@ -197,6 +252,150 @@ mod tests {
         assert!(results.iter().any(|c| c.sk == "a4"));
     }
 
+    #[tokio::test]
+    async fn test_query_with_sort_key_condition() {
+        use dynamode::agent::SortKeyCondition;
+
+        let agent = DynamodeAgent::connect_local().await;
+        let car1 = Car {
+            pk: "porsche".into(),
+            sk: "model-911".into(),
+            brand: "Porsche".into(),
+            model: "911".into(),
+            horsepower: 379,
+        };
+        let car2 = Car {
+            pk: "porsche".into(),
+            sk: "model-cayman".into(),
+            brand: "Porsche".into(),
+            model: "Cayman".into(),
+            horsepower: 300,
+        };
+        let car3 = Car {
+            pk: "porsche".into(),
+            sk: "other-taycan".into(),
+            brand: "Porsche".into(),
+            model: "Taycan".into(),
+            horsepower: 469,
+        };
+        agent.put(&car1).await.expect("Insert failed");
+        agent.put(&car2).await.expect("Insert failed");
+        agent.put(&car3).await.expect("Insert failed");
+
+        let results = agent
+            .query::<Car>(
+                "porsche".into(),
+                Some(SortKeyCondition::BeginsWith("model-".into())),
+            )
+            .await
+            .expect("Query failed");
+        assert!(results.iter().any(|c| c.sk == "model-911"));
+        assert!(results.iter().any(|c| c.sk == "model-cayman"));
+        assert!(!results.iter().any(|c| c.sk == "other-taycan"));
+    }
+
+    #[derive(Debug, Serialize, Deserialize)]
+    struct RawAttrs {
+        pk: String,
+        sk: String,
+        blob: serde_json::Value,
+        tags: serde_json::Value,
+        scores: serde_json::Value,
+        blobs: serde_json::Value,
+    }
+
+    #[async_trait::async_trait]
+    impl DynamoModel for RawAttrs {
+        fn table_name() -> &'static str {
+            "Cars"
+        }
+        fn partition_sort_key(&self) -> (String, String) {
+            (self.pk.clone(), self.sk.clone())
+        }
+        fn schema() -> TableSchema {
+            Car::schema()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_binary_and_set_conversion_round_trip() {
+        use base64::Engine;
+
+        let agent = DynamodeAgent::connect_local().await;
+        let blob_b64 = base64::engine::general_purpose::STANDARD.encode(b"hello");
+
+        let item = RawAttrs {
+            pk: "conv-test".into(),
+            sk: "attrs".into(),
+            blob: serde_json::json!({ "$binary": blob_b64 }),
+            tags: serde_json::json!({ "$stringSet": ["a", "b"] }),
+            scores: serde_json::json!({ "$numberSet": [1, 2, 3] }),
+            blobs: serde_json::json!({ "$binarySet": [blob_b64] }),
+        };
+        agent.put(&item).await.expect("Insert failed");
+
+        let fetched: RawAttrs = agent
+            .get::<RawAttrs>(("conv-test".into(), "attrs".into()))
+            .await
+            .expect("Get failed")
+            .expect("Item should exist");
+
+        // DynamoDB sets (`Ss`/`Ns`/`Bs`) don't preserve insertion order, so
+        // compare set-typed fields as sets rather than as ordered arrays.
+        fn as_set(value: &serde_json::Value, wrapper: &str) -> std::collections::HashSet<String> {
+            value[wrapper]
+                .as_array()
+                .expect("expected a JSON array")
+                .iter()
+                .map(|v| v.to_string())
+                .collect()
+        }
+
+        assert_eq!(fetched.blob, item.blob);
+        assert_eq!(as_set(&fetched.tags, "$stringSet"), as_set(&item.tags, "$stringSet"));
+        assert_eq!(
+            as_set(&fetched.scores, "$numberSet"),
+            as_set(&item.scores, "$numberSet")
+        );
+        assert_eq!(
+            as_set(&fetched.blobs, "$binarySet"),
+            as_set(&item.blobs, "$binarySet")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_query_by_index_brand() {
+        let agent = DynamodeAgent::connect_local().await;
+        agent
+            .migrate::<Car>()
+            .await
+            .expect("Migration should create the brand-index GSI");
+
+        let car1 = Car {
+            pk: "ferrari".into(),
+            sk: "f40".into(),
+            brand: "Ferrari".into(),
+            model: "F40".into(),
+            horsepower: 471,
+        };
+        let car2 = Car {
+            pk: "ferrari".into(),
+            sk: "enzo".into(),
+            brand: "Ferrari".into(),
+            model: "Enzo".into(),
+            horsepower: 660,
+        };
+        agent.put(&car1).await.expect("Insert failed");
+        agent.put(&car2).await.expect("Insert failed");
+
+        let results = agent
+            .query_by_index::<Car>(BRAND_INDEX, "Ferrari".into(), None)
+            .await
+            .expect("query_by_index failed");
+        assert!(results.iter().any(|c| c.sk == "f40"));
+        assert!(results.iter().any(|c| c.sk == "enzo"));
+    }
+
     #[tokio::test]
     async fn test_scan_all() {
         let agent = DynamodeAgent::connect_local().await;
@@ -205,4 +404,195 @@ mod tests {
         // This just checks that scan returns without error and gives a vector
         println!("Scan returned {} items.", results.len());
     }
+
+    #[tokio::test]
+    async fn test_batch_put_and_batch_delete() {
+        let agent = DynamodeAgent::connect_local().await;
+
+        // 30 items so the batch spans more than one BatchWriteItem chunk
+        // (DynamoDB's per-call limit is 25).
+        let cars: Vec<Car> = (0..30)
+            .map(|i| Car {
+                pk: "batch-brand".into(),
+                sk: format!("car-{i}"),
+                brand: "BatchBrand".into(),
+                model: format!("Model {i}"),
+                horsepower: 100 + i,
+            })
+            .collect();
+
+        agent.batch_put(&cars).await.expect("Batch put failed");
+
+        let results = agent
+            .query_by_pk::<Car>("batch-brand".into())
+            .await
+            .expect("Query failed");
+        assert_eq!(results.len(), 30);
+
+        let keys: Vec<(String, String)> = cars
+            .iter()
+            .map(|c| (c.pk.clone(), c.sk.clone()))
+            .collect();
+        agent
+            .batch_delete::<Car>(&keys)
+            .await
+            .expect("Batch delete failed");
+
+        let results = agent
+            .query_by_pk::<Car>("batch-brand".into())
+            .await
+            .expect("Query failed");
+        assert!(results.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_query_page_cursor_covers_all_items() {
+        let agent = DynamodeAgent::connect_local().await;
+
+        let cars: Vec<Car> = (0..10)
+            .map(|i| Car {
+                pk: "page-brand".into(),
+                sk: format!("car-{i}"),
+                brand: "PageBrand".into(),
+                model: format!("Model {i}"),
+                horsepower: 100 + i,
+            })
+            .collect();
+        agent.batch_put(&cars).await.expect("Batch put failed");
+
+        // Walk every page via the returned cursor until it runs dry, instead
+        // of assuming everything comes back in the first page.
+        let mut collected = Vec::new();
+        let mut cursor = None;
+        loop {
+            let (items, next_cursor) = agent
+                .query_page::<Car>("page-brand".into(), cursor)
+                .await
+                .expect("Query page failed");
+            collected.extend(items);
+            match next_cursor {
+                Some(c) => cursor = Some(c),
+                None => break,
+            }
+        }
+
+        assert_eq!(collected.len(), 10);
+        for car in &cars {
+            assert!(collected.iter().any(|c| c.sk == car.sk));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_put_if_condition_failed() {
+        let agent = DynamodeAgent::connect_local().await;
+        let car = Car {
+            pk: "mazda".into(),
+            sk: "mx5".into(),
+            brand: "Mazda".into(),
+            model: "MX-5".into(),
+            horsepower: 181,
+        };
+        agent.put(&car).await.expect("Insert failed");
+
+        let mut values = std::collections::HashMap::new();
+        values.insert(":hp".to_string(), serde_json::json!(9999));
+
+        let result = agent
+            .put_if(&car, "horsepower = :hp", values)
+            .await;
+        assert!(matches!(
+            result,
+            Err(dynamode::error::DynamodeError::ConditionFailed)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_put_if_condition_passes() {
+        let agent = DynamodeAgent::connect_local().await;
+        let car = Car {
+            pk: "mazda".into(),
+            sk: "rx7".into(),
+            brand: "Mazda".into(),
+            model: "RX-7".into(),
+            horsepower: 255,
+        };
+        agent.put(&car).await.expect("Insert failed");
+
+        let mut values = std::collections::HashMap::new();
+        values.insert(":hp".to_string(), serde_json::json!(255));
+
+        agent
+            .put_if(&car, "horsepower = :hp", values)
+            .await
+            .expect("Conditional put should have succeeded");
+    }
+
+    #[derive(Debug, Serialize, Deserialize)]
+    struct VersionedCar {
+        pk: String,
+        sk: String,
+        version: i64,
+    }
+
+    #[async_trait::async_trait]
+    impl DynamoModel for VersionedCar {
+        fn table_name() -> &'static str {
+            "Cars"
+        }
+        fn partition_sort_key(&self) -> (String, String) {
+            (self.pk.clone(), self.sk.clone())
+        }
+        fn schema() -> TableSchema {
+            Car::schema()
+        }
+        fn version(&self) -> Option<i64> {
+            Some(self.version)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_migrate_creates_table_idempotently() {
+        let agent = DynamodeAgent::connect_local().await;
+
+        // First call creates (or brings up to date) the table.
+        agent.migrate::<Car>().await.expect("Migration failed");
+
+        // The table should now be usable.
+        let car = Car {
+            pk: "migrate-brand".into(),
+            sk: "migrate-sk".into(),
+            brand: "MigrateBrand".into(),
+            model: "Model".into(),
+            horsepower: 100,
+        };
+        agent.put(&car).await.expect("Insert failed");
+
+        // Running the same migration again should be a no-op, not an error.
+        agent
+            .migrate::<Car>()
+            .await
+            .expect("Re-running migration should be idempotent");
+    }
+
+    #[tokio::test]
+    async fn test_put_versioned_conflict() {
+        let agent = DynamodeAgent::connect_local().await;
+        let item = VersionedCar {
+            pk: "lexus".into(),
+            sk: "lfa".into(),
+            version: 0,
+        };
+        agent
+            .put_versioned(&item)
+            .await
+            .expect("First versioned put should succeed");
+
+        // Same stale `version: 0` as before, but the stored item is now at
+        // version 1 - this write should lose the race.
+        let result = agent.put_versioned(&item).await;
+        assert!(matches!(
+            result,
+            Err(dynamode::error::DynamodeError::ConditionFailed)
+        ));
+    }
 }
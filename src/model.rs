@@ -1,4 +1,35 @@
 use async_trait::async_trait;
+use aws_sdk_dynamodb::types::{BillingMode, ScalarAttributeType};
+
+/// Declares one global secondary index for a `TableSchema`.
+pub struct IndexSchema {
+    pub name: &'static str,
+    pub hash_name: &'static str,
+    pub hash_type: ScalarAttributeType,
+    pub range_name: Option<&'static str>,
+    pub range_type: Option<ScalarAttributeType>,
+}
+
+/// Declares the physical shape of a model's table, driving
+/// `DynamodeAgent::migrate`.
+///
+/// `pk_type`/`sk_type` must be `ScalarAttributeType::S`: `partition_sort_key`
+/// and every key-building CRUD path (`get`, `put_if`, `delete`,
+/// `update_fields`, `query`, `query_by_pk`) work with `String` keys and
+/// always encode them as `AttributeValue::S`. `DynamodeAgent::migrate`
+/// rejects a schema declaring a numeric or binary primary key rather than
+/// build a table the rest of the agent can't read or write. GSIs don't have
+/// this restriction - `IndexSchema::hash_type`/`range_type` are honored by
+/// `query_by_index`.
+pub struct TableSchema {
+    pub table_name: &'static str,
+    pub pk_name: &'static str,
+    pub pk_type: ScalarAttributeType,
+    pub sk_name: Option<&'static str>,
+    pub sk_type: Option<ScalarAttributeType>,
+    pub billing_mode: BillingMode,
+    pub global_secondary_indexes: &'static [IndexSchema],
+}
 
 /// Trait for any struct to be used as a DynamoDB entity.
 #[async_trait]
@@ -7,4 +38,17 @@ pub trait DynamoModel: Send + Sync {
     where
         Self: Sized;
     fn partition_sort_key(&self) -> (String, String);
+
+    /// Declares this model's table shape (keys, billing mode, GSIs) for
+    /// `DynamodeAgent::migrate` to create or update.
+    fn schema() -> TableSchema
+    where
+        Self: Sized;
+
+    /// The caller's current `version` for optimistic-lock writes via
+    /// `DynamodeAgent::put_versioned`. Models that don't opt into versioning
+    /// can leave this as the default `None`.
+    fn version(&self) -> Option<i64> {
+        None
+    }
 }